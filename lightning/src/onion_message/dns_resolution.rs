@@ -15,11 +15,20 @@
 //! [bLIP 32]: https://github.com/lightning/blips/blob/master/blip-0032.md
 //! [`OnionMessenger`]: super::messenger::OnionMessenger
 
-use dnssec_prover::rr::Name;
+use bitcoin::hashes::{sha1, Hash, HashEngine};
+
+use dnssec_prover::query::ProofBuilder;
+use dnssec_prover::rr::{Name, RR};
+use dnssec_prover::validation::verify_rr_stream;
+
+use core::cmp;
+use core::fmt;
+use core::str::FromStr;
 
 use crate::blinded_path::message::DNSResolverContext;
 use crate::io;
 use crate::ln::msgs::DecodeError;
+use crate::offers::offer::Offer;
 use crate::onion_message::messenger::{MessageSendInstructions, Responder, ResponseInstruction};
 use crate::onion_message::packet::OnionMessageContents;
 use crate::prelude::*;
@@ -42,6 +51,21 @@ pub trait DNSResolverMessageHandler {
 	/// With this, we should be able to validate the DNS record we requested.
 	fn handle_dnssec_proof(&self, message: DNSSECProof, context: DNSResolverContext);
 
+	/// Handle an [`OfferRequest`] message.
+	///
+	/// If we provide DNS resolution services to third parties, we should resolve the
+	/// [`HumanReadableName`] it carries and, if it resolves to a BOLT 12 offer, respond with an
+	/// [`OfferResponse`] message.
+	fn handle_offer_request(
+		&self, message: OfferRequest, responder: Option<Responder>,
+	) -> Option<(DNSResolverMessage, ResponseInstruction)>;
+
+	/// Handle an [`OfferResponse`] message (in response to an [`OfferRequest`] we presumably sent).
+	///
+	/// With this, we should be able to verify the [`Offer`] against the backing [`DNSSECProof`] and
+	/// pay it.
+	fn handle_offer_response(&self, message: OfferResponse, context: DNSResolverContext);
+
 	/// Release any [`DNSResolverMessage`]s that need to be sent.
 	fn release_pending_messages(&self) -> Vec<(DNSResolverMessage, MessageSendInstructions)> {
 		vec![]
@@ -56,10 +80,16 @@ pub enum DNSResolverMessage {
 	DNSSECQuery(DNSSECQuery),
 	/// A response containing a DNSSEC proof
 	DNSSECProof(DNSSECProof),
+	/// A query requesting a BOLT 12 offer for a [`HumanReadableName`]
+	OfferRequest(OfferRequest),
+	/// A response containing a BOLT 12 offer and the DNSSEC proof backing it
+	OfferResponse(OfferResponse),
 }
 
 const DNSSEC_QUERY_TYPE: u64 = 65536;
 const DNSSEC_PROOF_TYPE: u64 = 65538;
+const OFFER_REQUEST_TYPE: u64 = 65540;
+const OFFER_RESPONSE_TYPE: u64 = 65542;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 /// A message which is sent to a DNSSEC prover requesting a DNSSEC proof for the given name.
@@ -74,14 +104,184 @@ pub struct DNSSECProof {
 	/// An [RFC 9102 DNSSEC AuthenticationChain] providing a DNSSEC proof.
 	///
 	/// [RFC 9102 DNSSEC AuthenticationChain]: https://www.rfc-editor.org/rfc/rfc9102.html#name-dnssec-authentication-chain
-	pub proof: Vec<u8>,
+	pub proof: ProofBuf,
+}
+
+/// A growable byte buffer which keeps its contents inline on the stack up to
+/// [`Self::INLINE_CAPACITY`] bytes, spilling to the heap only for larger proofs.
+///
+/// DNSSEC proofs are commonly only a kilobyte or two, so carrying them in a [`ProofBuf`] keeps
+/// no-std resolvers off the allocator on the hot path. The total length is capped at [`u16::MAX`];
+/// appends which would exceed it are rejected rather than silently truncated.
+#[derive(Clone)]
+pub struct ProofBuf(ProofStorage);
+
+#[derive(Clone)]
+enum ProofStorage {
+	Inline { data: [u8; ProofBuf::INLINE_CAPACITY], len: u16 },
+	Spilled(Vec<u8>),
+}
+
+impl ProofBuf {
+	/// The number of bytes a [`ProofBuf`] holds inline before it spills to the heap.
+	pub const INLINE_CAPACITY: usize = 2048;
+
+	/// Constructs a [`ProofBuf`] of `len` zero bytes, ready to be filled via [`Self::as_mut_slice`].
+	///
+	/// # Panics
+	///
+	/// Panics if `len` exceeds [`u16::MAX`], the cap the length prefix written by [`Writeable`] can
+	/// represent - without it the prefix would silently truncate while all bytes are still written,
+	/// producing an unparseable frame.
+	pub fn new_zeroed(len: usize) -> ProofBuf {
+		assert!(len <= u16::MAX as usize, "ProofBuf length is capped at u16::MAX");
+		if len <= Self::INLINE_CAPACITY {
+			ProofBuf(ProofStorage::Inline { data: [0; Self::INLINE_CAPACITY], len: len as u16 })
+		} else {
+			ProofBuf(ProofStorage::Spilled(vec![0; len]))
+		}
+	}
+
+	/// Appends `slice` to the buffer, spilling to the heap if it no longer fits inline.
+	///
+	/// Returns `Err(())` if the append would take the total length past [`u16::MAX`], leaving the
+	/// buffer unchanged, rather than silently truncating.
+	pub fn extend_from_slice(&mut self, slice: &[u8]) -> Result<(), ()> {
+		let new_len = self.len().checked_add(slice.len()).ok_or(())?;
+		if new_len > u16::MAX as usize {
+			return Err(());
+		}
+		match &mut self.0 {
+			ProofStorage::Inline { data, len } if new_len <= Self::INLINE_CAPACITY => {
+				data[*len as usize..new_len].copy_from_slice(slice);
+				*len = new_len as u16;
+			},
+			ProofStorage::Inline { data, len } => {
+				let mut spilled = Vec::with_capacity(new_len);
+				spilled.extend_from_slice(&data[..*len as usize]);
+				spilled.extend_from_slice(slice);
+				self.0 = ProofStorage::Spilled(spilled);
+			},
+			ProofStorage::Spilled(spilled) => spilled.extend_from_slice(slice),
+		}
+		Ok(())
+	}
+
+	/// The number of bytes currently held in the buffer.
+	pub fn len(&self) -> usize {
+		match &self.0 {
+			ProofStorage::Inline { len, .. } => *len as usize,
+			ProofStorage::Spilled(spilled) => spilled.len(),
+		}
+	}
+
+	/// Whether the buffer is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The buffer's contents as a byte slice.
+	pub fn as_slice(&self) -> &[u8] {
+		match &self.0 {
+			ProofStorage::Inline { data, len } => &data[..*len as usize],
+			ProofStorage::Spilled(spilled) => spilled.as_slice(),
+		}
+	}
+
+	/// The buffer's contents as a mutable byte slice.
+	pub fn as_mut_slice(&mut self) -> &mut [u8] {
+		match &mut self.0 {
+			ProofStorage::Inline { data, len } => &mut data[..*len as usize],
+			ProofStorage::Spilled(spilled) => spilled.as_mut_slice(),
+		}
+	}
+}
+
+impl TryFrom<Vec<u8>> for ProofBuf {
+	type Error = ();
+	fn try_from(bytes: Vec<u8>) -> Result<Self, ()> {
+		let mut buf = ProofBuf::new_zeroed(0);
+		buf.extend_from_slice(&bytes)?;
+		Ok(buf)
+	}
+}
+
+impl core::ops::Deref for ProofBuf {
+	type Target = [u8];
+	fn deref(&self) -> &[u8] {
+		self.as_slice()
+	}
+}
+
+impl PartialEq for ProofBuf {
+	fn eq(&self, other: &Self) -> bool {
+		self.as_slice() == other.as_slice()
+	}
+}
+impl Eq for ProofBuf {}
+
+impl core::hash::Hash for ProofBuf {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		self.as_slice().hash(state);
+	}
+}
+
+impl fmt::Debug for ProofBuf {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_slice().fmt(f)
+	}
+}
+
+impl Writeable for ProofBuf {
+	fn write<W: Writer>(&self, w: &mut W) -> Result<(), io::Error> {
+		(self.len() as u16).write(w)?;
+		w.write_all(self.as_slice())
+	}
+}
+
+impl Readable for ProofBuf {
+	fn read<R: io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+		let len: u16 = Readable::read(r)?;
+		let mut buf = ProofBuf::new_zeroed(len as usize);
+		r.read_exact(buf.as_mut_slice())?;
+		Ok(buf)
+	}
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+/// A message which is sent to a DNS resolver requesting a BOLT 12 [`Offer`] for the given
+/// [`HumanReadableName`].
+pub struct OfferRequest(pub HumanReadableName);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A message which is sent in response to [`OfferRequest`] containing a BOLT 12 [`Offer`] resolved
+/// from the requested [`HumanReadableName`].
+pub struct OfferResponse {
+	/// The [`Offer`] which the [`HumanReadableName`] in the [`OfferRequest`] resolved to.
+	pub offer: Offer,
+	/// The [RFC 9102 DNSSEC AuthenticationChain] backing the `user._bitcoin-payment.domain` record
+	/// from which [`Self::offer`] was parsed, allowing the requester to independently verify it.
+	///
+	/// [RFC 9102 DNSSEC AuthenticationChain]: https://www.rfc-editor.org/rfc/rfc9102.html#name-dnssec-authentication-chain
+	pub proof: ProofBuf,
+}
+
+// `Offer` does not implement `Hash`, so we hash over its serialized bytes (as used on the wire)
+// rather than deriving it, keeping `DNSResolverMessage` hashable.
+impl core::hash::Hash for OfferResponse {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		let mut offer_bytes = Vec::new();
+		self.offer.write(&mut offer_bytes).expect("Writing to a Vec can't fail");
+		offer_bytes.hash(state);
+		self.proof.hash(state);
+	}
 }
 
 impl DNSResolverMessage {
 	/// Returns whether `tlv_type` corresponds to a TLV record for DNS Resolvers.
 	pub fn is_known_type(tlv_type: u64) -> bool {
 		match tlv_type {
-			DNSSEC_QUERY_TYPE | DNSSEC_PROOF_TYPE => true,
+			DNSSEC_QUERY_TYPE | DNSSEC_PROOF_TYPE | OFFER_REQUEST_TYPE | OFFER_RESPONSE_TYPE => true,
 			_ => false,
 		}
 	}
@@ -99,6 +299,14 @@ impl Writeable for DNSResolverMessage {
 				w.write_all(&name.as_str().as_bytes())?;
 				proof.write(w)
 			},
+			Self::OfferRequest(OfferRequest(hrn)) => hrn.write(w),
+			Self::OfferResponse(OfferResponse { offer, proof }) => {
+				let mut offer_bytes = Vec::new();
+				offer.write(&mut offer_bytes).expect("Writing to a Vec can't fail");
+				(offer_bytes.len() as u16).write(w)?;
+				w.write_all(&offer_bytes)?;
+				proof.write(w)
+			},
 		}
 	}
 }
@@ -117,6 +325,18 @@ impl ReadableArgs<u64> for DNSResolverMessage {
 				let proof = Readable::read(r)?;
 				Ok(DNSResolverMessage::DNSSECProof(DNSSECProof { name, proof }))
 			},
+			OFFER_REQUEST_TYPE => {
+				let hrn = Readable::read(r)?;
+				Ok(DNSResolverMessage::OfferRequest(OfferRequest(hrn)))
+			},
+			OFFER_RESPONSE_TYPE => {
+				let offer_len: u16 = Readable::read(r)?;
+				let mut offer_bytes = vec![0; offer_len as usize];
+				r.read_exact(&mut offer_bytes)?;
+				let offer = Offer::try_from(offer_bytes).map_err(|_| DecodeError::InvalidValue)?;
+				let proof = Readable::read(r)?;
+				Ok(DNSResolverMessage::OfferResponse(OfferResponse { offer, proof }))
+			},
 			_ => Err(DecodeError::InvalidValue),
 		}
 	}
@@ -128,6 +348,8 @@ impl OnionMessageContents for DNSResolverMessage {
 		match self {
 			DNSResolverMessage::DNSSECQuery(_) => "DNS(SEC) Query".to_string(),
 			DNSResolverMessage::DNSSECProof(_) => "DNSSEC Proof".to_string(),
+			DNSResolverMessage::OfferRequest(_) => "Offer Request".to_string(),
+			DNSResolverMessage::OfferResponse(_) => "Offer Response".to_string(),
 		}
 	}
 	#[cfg(not(c_bindings))]
@@ -135,14 +357,370 @@ impl OnionMessageContents for DNSResolverMessage {
 		match self {
 			DNSResolverMessage::DNSSECQuery(_) => "DNS(SEC) Query",
 			DNSResolverMessage::DNSSECProof(_) => "DNSSEC Proof",
+			DNSResolverMessage::OfferRequest(_) => "Offer Request",
+			DNSResolverMessage::OfferResponse(_) => "Offer Response",
 		}
 	}
 	fn tlv_type(&self) -> u64 {
 		match self {
 			DNSResolverMessage::DNSSECQuery(_) => DNSSEC_QUERY_TYPE,
 			DNSResolverMessage::DNSSECProof(_) => DNSSEC_PROOF_TYPE,
+			DNSResolverMessage::OfferRequest(_) => OFFER_REQUEST_TYPE,
+			DNSResolverMessage::OfferResponse(_) => OFFER_RESPONSE_TYPE,
+		}
+	}
+}
+
+/// The result of validating a negative (proof-of-non-existence) [`DNSSECProof`].
+///
+/// A negative proof authenticates the *absence* of a record - e.g. that a given
+/// `user._bitcoin-payment.domain` has no TXT record - by way of the signed NSEC/NSEC3 chain drawn
+/// from the DNS authority section.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct NegativeProof {
+	/// The minimum TTL, in seconds, across all records in the NSEC/NSEC3 chain.
+	///
+	/// This bounds how long the negative result may be cached before it must be re-fetched.
+	pub min_ttl: u32,
+}
+
+impl DNSSECProof {
+	/// Validates this proof as a proof-of-non-existence for `name`.
+	///
+	/// [`Self::proof`] must be a full RFC 9102 chain which validates against the hardcoded root trust
+	/// anchors; an unsigned NSEC/NSEC3 record is not trusted. Once the chain is authenticated, the
+	/// signed NSEC/NSEC3 records it carries must cover `name` - that is, `name` must sort strictly
+	/// between an NSEC owner and its `next` field (or, for NSEC3, the base32hex-encoded salted hash
+	/// of `name` must fall in a covered interval).
+	///
+	/// Beyond the DNSKEY/DS records needed to build the chain, the authenticated set must contain
+	/// only NSEC/NSEC3 records and their RRSIGs - any other record (e.g. a smuggled positive TXT/A
+	/// answer) causes the proof to be rejected.
+	///
+	/// On success the caller may authenticate a negative answer for `name`, valid for
+	/// [`NegativeProof::min_ttl`] seconds. Note that `min_ttl` is the minimum across the NSEC/NSEC3
+	/// records and their RRSIGs only; `dnssec-prover` does not surface the accompanying SOA record, so
+	/// its RFC 2308 negative-cache MINIMUM cannot be folded in here.
+	pub fn verify_nonexistence(&self, name: &Name) -> Result<NegativeProof, ()> {
+		let verified = verify_rr_stream(&self.proof).map_err(|_| ())?;
+		let mut min_ttl = u32::MAX;
+		let mut covered = false;
+		for rr in &verified.verified_rrs {
+			match rr {
+				RR::NSec(nsec) => {
+					min_ttl = cmp::min(min_ttl, nsec.ttl);
+					if nsec_covers(name, &nsec.name, &nsec.next_name) {
+						covered = true;
+					}
+				},
+				RR::NSec3(nsec3) => {
+					min_ttl = cmp::min(min_ttl, nsec3.ttl);
+					if nsec3_covers(name, nsec3)? {
+						covered = true;
+					}
+				},
+				RR::RRSig(rrsig) => {
+					min_ttl = cmp::min(min_ttl, rrsig.ttl);
+				},
+				// DNSKEY/DS records are part of the chain that `verify_rr_stream` authenticated above;
+				// they're expected and carry no negative-answer meaning.
+				RR::DnsKey(_) | RR::DS(_) => {},
+				// Anything else in the authenticated set is an answer/authority record that has no
+				// business accompanying a proof-of-non-existence; reject so a positive record can't be
+				// smuggled alongside the negative answer.
+				_ => return Err(()),
+			}
+		}
+		if !covered || min_ttl == u32::MAX {
+			return Err(());
+		}
+		Ok(NegativeProof { min_ttl })
+	}
+}
+
+/// Compares two DNS names in [canonical DNS name order], i.e. label-by-label from the root up, each
+/// label compared as a case-insensitive sequence of octets.
+///
+/// [canonical DNS name order]: https://www.rfc-editor.org/rfc/rfc4034.html#section-6.1
+fn canonical_name_cmp(a: &Name, b: &Name) -> cmp::Ordering {
+	let a_labels: Vec<&str> = a.as_str().trim_end_matches('.').split('.').rev().collect();
+	let b_labels: Vec<&str> = b.as_str().trim_end_matches('.').split('.').rev().collect();
+	for (al, bl) in a_labels.iter().zip(b_labels.iter()) {
+		for (ac, bc) in al.bytes().zip(bl.bytes()) {
+			match ac.to_ascii_lowercase().cmp(&bc.to_ascii_lowercase()) {
+				cmp::Ordering::Equal => {},
+				non_eq => return non_eq,
+			}
+		}
+		match al.len().cmp(&bl.len()) {
+			cmp::Ordering::Equal => {},
+			non_eq => return non_eq,
 		}
 	}
+	a_labels.len().cmp(&b_labels.len())
+}
+
+/// Returns whether the NSEC record owned by `owner` with the given `next` owner covers `name`, i.e.
+/// proves that no record exists at `name`.
+fn nsec_covers(name: &Name, owner: &Name, next: &Name) -> bool {
+	match canonical_name_cmp(owner, next) {
+		// The common case: `name` sorts strictly between the owner and its successor.
+		cmp::Ordering::Less => {
+			canonical_name_cmp(owner, name) == cmp::Ordering::Less
+				&& canonical_name_cmp(name, next) == cmp::Ordering::Less
+		},
+		// The last NSEC in the zone wraps around to the apex, covering everything after `owner`.
+		cmp::Ordering::Greater | cmp::Ordering::Equal => {
+			canonical_name_cmp(owner, name) == cmp::Ordering::Less
+				|| canonical_name_cmp(name, next) == cmp::Ordering::Less
+		},
+	}
+}
+
+/// Computes the NSEC3 hash of `name` - `iterations + 1` rounds of SHA-1 over the canonical wire-form
+/// name concatenated with `salt`, per [RFC 5155].
+///
+/// [RFC 5155]: https://www.rfc-editor.org/rfc/rfc5155.html#section-5
+fn nsec3_hash(name: &Name, salt: &[u8], iterations: u16) -> [u8; 20] {
+	fn hash(data: &[u8], salt: &[u8]) -> [u8; 20] {
+		let mut engine = sha1::Hash::engine();
+		engine.input(data);
+		engine.input(salt);
+		sha1::Hash::from_engine(engine).to_byte_array()
+	}
+	let mut wire = Vec::new();
+	for label in name.as_str().trim_end_matches('.').split('.') {
+		wire.push(label.len() as u8);
+		wire.extend_from_slice(&label.to_ascii_lowercase().into_bytes());
+	}
+	wire.push(0);
+	let mut digest = hash(&wire, salt);
+	for _ in 0..iterations {
+		digest = hash(&digest, salt);
+	}
+	digest
+}
+
+/// base32hex (RFC 4648 "Extended Hex") encoding without padding, as used for NSEC3 owner names.
+fn base32hex(data: &[u8]) -> String {
+	const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+	let mut out = String::with_capacity((data.len() * 8 + 4) / 5);
+	let mut buffer = 0u64;
+	let mut bits = 0u32;
+	for &byte in data {
+		buffer = (buffer << 8) | byte as u64;
+		bits += 8;
+		while bits >= 5 {
+			bits -= 5;
+			out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+		}
+	}
+	if bits > 0 {
+		out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+	}
+	out
+}
+
+/// Returns whether the given NSEC3 record covers `name`, i.e. the salted hash of `name` sorts
+/// strictly between this record's owner hash and its `next` hash (both endpoints excluded).
+fn nsec3_covers(name: &Name, nsec3: &dnssec_prover::rr::NSec3) -> Result<bool, ()> {
+	if nsec3.hash_algo != 1 {
+		// Only SHA-1 (algorithm 1) is defined for NSEC3.
+		return Err(());
+	}
+	let name_hash = base32hex(&nsec3_hash(name, &nsec3.salt, nsec3.hash_iterations));
+	// The NSEC3 owner name's first label is the base32hex hash of the owner.
+	let owner_hash = nsec3
+		.name
+		.as_str()
+		.split('.')
+		.next()
+		.ok_or(())?
+		.to_ascii_uppercase();
+	let next_hash = base32hex(&nsec3.next_name_hash);
+	Ok(if owner_hash < next_hash {
+		owner_hash < name_hash && name_hash < next_hash
+	} else {
+		// Wrap-around at the last NSEC3 in the zone.
+		owner_hash < name_hash || name_hash < next_hash
+	})
+}
+
+/// The DNS TXT record type, the record type BIP 353 payment instructions are published under.
+const TXT_TYPE: u16 = 16;
+
+/// The BIP 353 payment instructions extracted from a proven `user._bitcoin-payment.domain` TXT
+/// record.
+///
+/// At least one of the fields is guaranteed to be present - a `bitcoin:` URI with no payment target
+/// is rejected during parsing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bip353PaymentInstructions {
+	/// The BOLT 12 [`Offer`] parsed from the `lno=` URI parameter, if any.
+	pub offer: Option<Offer>,
+	/// The on-chain address from the URI body, if any.
+	pub onchain_address: Option<String>,
+	/// The BOLT 11 invoice from the `lightning=` URI parameter, if any.
+	pub bolt11_invoice: Option<String>,
+}
+
+impl DNSSECProof {
+	/// Extracts the BIP 353 payment instructions for `hrn` from this proof.
+	///
+	/// This validates the proof against the hardcoded root trust anchors, finds a
+	/// `user._bitcoin-payment.domain` TXT record matching `hrn` (skipping any other record the proof
+	/// happens to carry), parses its payload as a `bitcoin:` URI, and returns its payment targets as
+	/// typed values.
+	///
+	/// The homograph protections enforced by [`HumanReadableName::new`] apply, as `hrn` is already
+	/// constrained to plain ASCII.
+	pub fn resolve_payment_instructions(
+		&self, hrn: &HumanReadableName,
+	) -> Result<Bip353PaymentInstructions, ()> {
+		let verified = verify_rr_stream(&self.proof).map_err(|_| ())?;
+		let expected = format!("{}._bitcoin-payment.{}.", hrn.user(), hrn.domain());
+		for rr in verified.resolve_name(&self.name) {
+			let txt = match rr {
+				RR::Txt(txt) => txt,
+				_ => continue,
+			};
+			// The proof may legitimately carry unrelated TXT records (or a wildcard-expanded owner
+			// name); skip anything that isn't the record we asked for rather than aborting the lookup.
+			// DNS owner names are case-insensitive, so compare as ASCII regardless of case.
+			if !txt.name.as_str().eq_ignore_ascii_case(&expected) {
+				continue;
+			}
+			// `dnssec-prover` already strips the per-string length octets from TXT RDATA, so
+			// `txt.data` is the concatenated payload - matching how `handle_dnssec_proof` surfaces it.
+			let uri = match core::str::from_utf8(&txt.data) {
+				Ok(uri) => uri,
+				Err(_) => continue,
+			};
+			if let Some(instructions) = parse_bitcoin_uri(uri) {
+				return Ok(instructions);
+			}
+		}
+		Err(())
+	}
+}
+
+/// Parses a BIP 21 `bitcoin:` URI into its typed payment targets, returning `None` if it is not a
+/// `bitcoin:` URI or carries no payment target at all.
+fn parse_bitcoin_uri(uri: &str) -> Option<Bip353PaymentInstructions> {
+	let body = uri.strip_prefix("bitcoin:")?;
+	let (address, query) = match body.split_once('?') {
+		Some((address, query)) => (address, query),
+		None => (body, ""),
+	};
+	let onchain_address = if address.is_empty() { None } else { Some(address.to_string()) };
+
+	let mut offer = None;
+	let mut bolt11_invoice = None;
+	for param in query.split('&').filter(|p| !p.is_empty()) {
+		// A valueless parameter (e.g. `req-foo`) carries no target we understand; skip it rather than
+		// discarding an otherwise-payable URI.
+		let (key, value) = match param.split_once('=') {
+			Some(kv) => kv,
+			None => continue,
+		};
+		match key {
+			// An unparseable offer must not sink the whole URI - leave `offer` unset so any on-chain
+			// address or BOLT 11 fallback in the same URI still gets used.
+			"lno" => offer = Offer::from_str(value).ok(),
+			"lightning" => bolt11_invoice = Some(value.to_string()),
+			_ => {},
+		}
+	}
+
+	if offer.is_none() && onchain_address.is_none() && bolt11_invoice.is_none() {
+		return None;
+	}
+	Some(Bip353PaymentInstructions { offer, onchain_address, bolt11_invoice })
+}
+
+/// A source of authoritative DNS responses used when building RFC 9102 proofs.
+///
+/// Implementations forward the wire-format `query` to a configurable upstream resolver - which, per
+/// RFC 9102, must be reached over DNS-over-HTTPS - and return the raw wire-format response.
+pub trait UpstreamDNSResolver {
+	/// Resolves a single wire-format DNS `query`, returning the wire-format response.
+	fn resolve_query(&self, query: &[u8]) -> Result<Vec<u8>, ()>;
+}
+
+/// A ready-made [`DNSResolverMessageHandler`] which builds and validates RFC 9102 proofs using
+/// [`dnssec-prover`], turning the [`DNSResolverMessageHandler`] trait into something usable out of
+/// the box rather than an integration stub.
+///
+/// When acting as a resolver, [`Self`] answers [`DNSSECQuery`]s by driving a [`ProofBuilder`] against
+/// the configured [`UpstreamDNSResolver`] (queries are built with a transaction ID of 0 and the
+/// Recursive-Desired/Authenticated-Data flags, as required for DNS-over-HTTPS transports) and
+/// replies with the assembled [`DNSSECProof`].
+///
+/// When acting as a requester, [`Self`] validates received [`DNSSECProof`]s against the hardcoded
+/// root trust anchors, confirms the proof covers the requested name (including wildcard expansion),
+/// and surfaces the validated TXT record set to `txt_handler`.
+///
+/// [`dnssec-prover`]: https://crates.io/crates/dnssec-prover
+pub struct DNSSECResolver<U: UpstreamDNSResolver, H: Fn(&Name, Vec<Vec<u8>>)> {
+	upstream: U,
+	txt_handler: H,
+}
+
+impl<U: UpstreamDNSResolver, H: Fn(&Name, Vec<Vec<u8>>)> DNSSECResolver<U, H> {
+	/// Constructs a new [`DNSSECResolver`] forwarding queries to `upstream` and surfacing validated
+	/// TXT records to `txt_handler`.
+	pub fn new(upstream: U, txt_handler: H) -> Self {
+		DNSSECResolver { upstream, txt_handler }
+	}
+
+	/// Builds an RFC 9102 proof for the TXT records at `name` by repeatedly querying the upstream
+	/// resolver until the [`ProofBuilder`] has everything it needs.
+	fn build_proof(&self, name: &Name) -> Result<ProofBuf, ()> {
+		let (mut builder, initial_query) = ProofBuilder::new(name, TXT_TYPE);
+		let mut pending_queries = vec![initial_query];
+		while let Some(query) = pending_queries.pop() {
+			let response = self.upstream.resolve_query(&query)?;
+			pending_queries.append(&mut builder.process_response(&response)?);
+		}
+		let (proof, _min_ttl) = builder.finish_proof().map_err(|_| ())?;
+		ProofBuf::try_from(proof)
+	}
+}
+
+impl<U: UpstreamDNSResolver, H: Fn(&Name, Vec<Vec<u8>>)> DNSResolverMessageHandler
+	for DNSSECResolver<U, H>
+{
+	fn handle_dnssec_query(
+		&self, message: DNSSECQuery, responder: Option<Responder>,
+	) -> Option<(DNSResolverMessage, ResponseInstruction)> {
+		let responder = responder?;
+		let DNSSECQuery(name) = message;
+		let proof = self.build_proof(&name).ok()?;
+		let response = DNSResolverMessage::DNSSECProof(DNSSECProof { name, proof });
+		Some((response, responder.respond()))
+	}
+
+	fn handle_dnssec_proof(&self, message: DNSSECProof, _context: DNSResolverContext) {
+		let verified = match verify_rr_stream(&message.proof) {
+			Ok(verified) => verified,
+			Err(_) => return,
+		};
+		let resolved = verified.resolve_name(&message.name);
+		let txts = resolved
+			.iter()
+			.filter_map(|rr| if let RR::Txt(txt) = rr { Some(txt.data.clone()) } else { None })
+			.collect();
+		(self.txt_handler)(&message.name, txts);
+	}
+
+	fn handle_offer_request(
+		&self, _message: OfferRequest, _responder: Option<Responder>,
+	) -> Option<(DNSResolverMessage, ResponseInstruction)> {
+		// This resolver only provides raw DNSSEC proofs; offer resolution is left to the caller.
+		None
+	}
+
+	fn handle_offer_response(&self, _message: OfferResponse, _context: DNSResolverContext) {}
 }
 
 /// A struct containing the two parts of a BIP 353 Human Readable Name - the user and domain parts.
@@ -154,11 +732,14 @@ impl OnionMessageContents for DNSResolverMessage {
 /// ASCII.
 ///
 /// [Homograph Attacks]: https://en.wikipedia.org/wiki/IDN_homograph_attack
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy)]
 pub struct HumanReadableName {
-	// TODO Remove the heap allocations given the whole data can't be more than 256 bytes.
-	user: String,
-	domain: String,
+	// The `user` and `domain` parts are stored back-to-back in `contents`, with `user` occupying the
+	// first `user_len` bytes and `domain` the following `domain_len` bytes. As their combined length
+	// is bounded to 255 bytes this avoids any heap allocation and keeps the type `Copy`.
+	contents: [u8; 255],
+	user_len: u8,
+	domain_len: u8,
 }
 
 impl HumanReadableName {
@@ -175,7 +756,14 @@ impl HumanReadableName {
 		if !Hostname::str_is_valid_hostname(&user) || !Hostname::str_is_valid_hostname(&domain) {
 			return Err(());
 		}
-		Ok(HumanReadableName { user, domain })
+		let mut contents = [0; 255];
+		contents[..user.len()].copy_from_slice(user.as_bytes());
+		contents[user.len()..user.len() + domain.len()].copy_from_slice(domain.as_bytes());
+		Ok(HumanReadableName {
+			contents,
+			user_len: user.len() as u8,
+			domain_len: domain.len() as u8,
+		})
 	}
 
 	/// Constructs a new [`HumanReadableName`] from the standard encoding - `user`@`domain`.
@@ -193,22 +781,49 @@ impl HumanReadableName {
 
 	/// Gets the `user` part of this Human Readable Name
 	pub fn user(&self) -> &str {
-		&self.user
+		let bytes = &self.contents[..self.user_len as usize];
+		core::str::from_utf8(bytes).expect("Checked to be valid UTF-8 on construction")
 	}
 
 	/// Gets the `domain` part of this Human Readable Name
 	pub fn domain(&self) -> &str {
-		&self.domain
+		let start = self.user_len as usize;
+		let end = start + self.domain_len as usize;
+		core::str::from_utf8(&self.contents[start..end])
+			.expect("Checked to be valid UTF-8 on construction")
+	}
+}
+
+impl PartialEq for HumanReadableName {
+	fn eq(&self, other: &Self) -> bool {
+		self.user() == other.user() && self.domain() == other.domain()
+	}
+}
+impl Eq for HumanReadableName {}
+
+impl core::hash::Hash for HumanReadableName {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		self.user().hash(state);
+		self.domain().hash(state);
+	}
+}
+
+impl fmt::Debug for HumanReadableName {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("HumanReadableName")
+			.field("user", &self.user())
+			.field("domain", &self.domain())
+			.finish()
 	}
 }
 
 // Serialized per the requirements for inclusion in a BOLT 12 `invoice_request`
 impl Writeable for HumanReadableName {
 	fn write<W: Writer>(&self, writer: &mut W) -> Result<(), io::Error> {
-		(self.user.len() as u8).write(writer)?;
-		writer.write_all(&self.user.as_bytes())?;
-		(self.domain.len() as u8).write(writer)?;
-		writer.write_all(&self.domain.as_bytes())
+		(self.user_len).write(writer)?;
+		writer.write_all(self.user().as_bytes())?;
+		(self.domain_len).write(writer)?;
+		writer.write_all(self.domain().as_bytes())
 	}
 }
 
@@ -235,3 +850,115 @@ impl Readable for HumanReadableName {
 		HumanReadableName::new(user, domain).map_err(|()| DecodeError::InvalidValue)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn name(s: &str) -> Name {
+		Name::try_from(s.to_string()).unwrap()
+	}
+
+	#[test]
+	fn base32hex_known_answers() {
+		// RFC 4648 test vectors, encoded as base32hex without padding.
+		assert_eq!(base32hex(b""), "");
+		assert_eq!(base32hex(b"f"), "CO");
+		assert_eq!(base32hex(b"fo"), "CPNG");
+		assert_eq!(base32hex(b"foo"), "CPNMU");
+		assert_eq!(base32hex(b"foob"), "CPNMUOG");
+		assert_eq!(base32hex(b"fooba"), "CPNMUOJ1");
+		assert_eq!(base32hex(b"foobar"), "CPNMUOJ1E8");
+	}
+
+	#[test]
+	fn nsec3_hash_respects_iterations() {
+		// The iteration count feeds extra SHA-1 rounds, so distinct counts must give distinct hashes.
+		let zero = nsec3_hash(&name("example.com."), b"", 0);
+		let one = nsec3_hash(&name("example.com."), b"", 1);
+		let two = nsec3_hash(&name("example.com."), b"", 2);
+		assert_ne!(zero, one);
+		assert_ne!(one, two);
+		// Names are hashed case-insensitively on their canonical wire form.
+		assert_eq!(zero, nsec3_hash(&name("EXAMPLE.COM."), b"", 0));
+		// A non-empty salt changes the result.
+		assert_ne!(zero, nsec3_hash(&name("example.com."), b"salt", 0));
+	}
+
+	#[test]
+	fn canonical_name_cmp_orders_from_the_root_up() {
+		use cmp::Ordering;
+		assert_eq!(canonical_name_cmp(&name("example.com."), &name("example.com.")), Ordering::Equal);
+		// Comparison is case-insensitive.
+		assert_eq!(canonical_name_cmp(&name("Example.Com."), &name("example.com.")), Ordering::Equal);
+		// A label closer to the root dominates one further from it.
+		assert_eq!(canonical_name_cmp(&name("z.example.com."), &name("a.example.org.")), Ordering::Less);
+		// Shorter labels sort before longer ones sharing a prefix.
+		assert_eq!(canonical_name_cmp(&name("a.example.com."), &name("ab.example.com.")), Ordering::Less);
+		// A name is greater than its own parent (more labels).
+		assert_eq!(canonical_name_cmp(&name("a.example.com."), &name("example.com.")), Ordering::Greater);
+	}
+
+	#[test]
+	fn nsec_covers_handles_wrap_around() {
+		// The common case: the queried name sorts strictly between the owner and its successor.
+		assert!(nsec_covers(&name("b.example.com."), &name("a.example.com."), &name("c.example.com.")));
+		assert!(!nsec_covers(&name("d.example.com."), &name("a.example.com."), &name("c.example.com.")));
+		// Endpoints are excluded (the interval is open).
+		assert!(!nsec_covers(&name("a.example.com."), &name("a.example.com."), &name("c.example.com.")));
+		assert!(!nsec_covers(&name("c.example.com."), &name("a.example.com."), &name("c.example.com.")));
+		// The last NSEC in the zone wraps: owner > next, so everything after the owner is covered.
+		assert!(nsec_covers(&name("z.example.com."), &name("m.example.com."), &name("example.com.")));
+		assert!(!nsec_covers(&name("b.example.com."), &name("m.example.com."), &name("example.com.")));
+	}
+
+	#[test]
+	fn proofbuf_inline_to_spill_boundary() {
+		// Filling exactly to INLINE_CAPACITY stays inline; one more byte spills to the heap, and the
+		// contents survive the transition intact.
+		let mut buf = ProofBuf::new_zeroed(0);
+		buf.extend_from_slice(&vec![1u8; ProofBuf::INLINE_CAPACITY]).unwrap();
+		assert!(matches!(buf.0, ProofStorage::Inline { .. }));
+		assert_eq!(buf.len(), ProofBuf::INLINE_CAPACITY);
+
+		buf.extend_from_slice(&[2u8]).unwrap();
+		assert!(matches!(buf.0, ProofStorage::Spilled(_)));
+		assert_eq!(buf.len(), ProofBuf::INLINE_CAPACITY + 1);
+		assert!(buf.as_slice()[..ProofBuf::INLINE_CAPACITY].iter().all(|&b| b == 1));
+		assert_eq!(buf.as_slice()[ProofBuf::INLINE_CAPACITY], 2);
+	}
+
+	#[test]
+	fn proofbuf_rejects_oversize_append() {
+		let mut buf = ProofBuf::new_zeroed(0);
+		assert!(buf.extend_from_slice(&vec![0u8; u16::MAX as usize]).is_ok());
+		// One byte past u16::MAX is rejected, leaving the buffer unchanged rather than truncating.
+		assert!(buf.extend_from_slice(&[0u8]).is_err());
+		assert_eq!(buf.len(), u16::MAX as usize);
+	}
+
+	#[test]
+	#[should_panic(expected = "capped at u16::MAX")]
+	fn proofbuf_new_zeroed_enforces_cap() {
+		ProofBuf::new_zeroed(u16::MAX as usize + 1);
+	}
+
+	#[test]
+	fn parse_bitcoin_uri_skips_valueless_params() {
+		// A valueless `req-` parameter must not discard an otherwise-payable on-chain address.
+		let parsed = parse_bitcoin_uri("bitcoin:bc1qaddress?req-something&label=foo").unwrap();
+		assert_eq!(parsed.onchain_address.as_deref(), Some("bc1qaddress"));
+		assert!(parsed.offer.is_none());
+		assert!(parsed.bolt11_invoice.is_none());
+	}
+
+	#[test]
+	fn parse_bitcoin_uri_requires_a_target() {
+		// Not a bitcoin: URI, and a bitcoin: URI with no address or recognised param, both fail.
+		assert!(parse_bitcoin_uri("https://example.com").is_none());
+		assert!(parse_bitcoin_uri("bitcoin:?label=foo").is_none());
+		// A bare on-chain address with no query is accepted.
+		let parsed = parse_bitcoin_uri("bitcoin:bc1qaddress").unwrap();
+		assert_eq!(parsed.onchain_address.as_deref(), Some("bc1qaddress"));
+	}
+}